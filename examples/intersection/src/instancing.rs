@@ -0,0 +1,299 @@
+//! GPU-instanced cuboid rendering: draws each surface voxel (a solid voxel
+//! with at least one exposed face) of a `CVoxels` object as a unit cube,
+//! scaled to `dx`, via per-instance transform + color data, instead of
+//! baking a single surface `Mesh` asset per object. Adapted from Bevy's own
+//! `shader_instancing` example, specialized to read instance data out of
+//! `CVoxelComponent::inner` instead of a fixed array.
+
+use bevy::{
+    core_pipeline::core_3d::Transparent3d,
+    ecs::system::{lifetimeless::*, SystemParamItem},
+    pbr::{MeshPipeline, MeshPipelineKey, RenderMeshInstances, SetMeshBindGroup, SetMeshViewBindGroup},
+    prelude::*,
+    render::{
+        extract_component::{ExtractComponent, ExtractComponentPlugin},
+        mesh::{GpuBufferInfo, MeshVertexBufferLayoutRef},
+        render_asset::RenderAssets,
+        render_phase::{
+            AddRenderCommand, DrawFunctions, PhaseItem, RenderCommand, RenderCommandResult,
+            SetItemPipeline, TrackedRenderPass, ViewSortedRenderPhases,
+        },
+        render_resource::*,
+        renderer::RenderDevice,
+        view::ExtractedView,
+        Render, RenderApp, RenderSet,
+    },
+};
+use bytemuck::{Pod, Zeroable};
+use cvoxel::CVoxels;
+
+use crate::CVoxelComponent;
+
+/// Per-instance data uploaded to the GPU: the voxel's center in the object's
+/// local space + uniform scale, and a linear color.
+#[derive(Clone, Copy, Pod, Zeroable)]
+#[repr(C)]
+struct InstanceData {
+    position: Vec3,
+    scale: f32,
+    color: [f32; 4],
+}
+
+/// Main-world instance list, rebuilt from the owning [`CVoxelComponent`]
+/// whenever its voxels change. Extracted into the render world and turned
+/// into a GPU buffer by [`prepare_instance_buffers`].
+#[derive(Component, Clone)]
+struct VoxelInstanceData(Vec<InstanceData>);
+
+impl ExtractComponent for VoxelInstanceData {
+    type QueryData = &'static VoxelInstanceData;
+    // Only re-extract (and thus re-mark `Changed` in the render world) when
+    // the main-world instance list actually changed, so
+    // `prepare_instance_buffers` doesn't re-upload a static object's buffer
+    // every frame.
+    type QueryFilter = Changed<VoxelInstanceData>;
+    type Out = Self;
+
+    fn extract_component(item: &VoxelInstanceData) -> Option<Self> {
+        Some(item.clone())
+    }
+}
+
+/// Marker for entities that should be drawn via the instanced cuboid path
+/// instead of a baked `surface_mesh()` asset.
+#[derive(Component)]
+pub struct InstancedVoxels;
+
+/// Rebuilds `VoxelInstanceData` from `cvoxel.inner.surface_instances()`
+/// whenever the component changes (e.g. after an edit or CSG op), so the
+/// GPU buffer is re-uploaded without ever touching a `Mesh` asset.
+fn update_instances_from_cvoxel(
+    mut commands: Commands,
+    voxels: Query<(Entity, &CVoxelComponent), (With<InstancedVoxels>, Changed<CVoxelComponent>)>,
+) {
+    for (entity, cvoxel) in &voxels {
+        let instances: Vec<InstanceData> = cvoxel
+            .inner
+            .surface_instances()
+            .map(|(center, color)| InstanceData {
+                position: Vec3::new(center.x, center.y, center.z),
+                scale: cvoxel.inner.dx,
+                color,
+            })
+            .collect();
+        commands.entity(entity).insert(VoxelInstanceData(instances));
+    }
+}
+
+/// Spawns one instanced-rendering entity for `cvoxel`, sharing a single unit
+/// cube mesh asset across every voxel of the object.
+pub fn spawn_instanced(commands: &mut Commands, meshes: &mut Assets<Mesh>, cvoxel: CVoxels) {
+    commands.spawn((
+        meshes.add(Cuboid::new(1.0, 1.0, 1.0)),
+        Transform::default(),
+        GlobalTransform::default(),
+        Visibility::default(),
+        InheritedVisibility::default(),
+        ViewVisibility::default(),
+        VoxelInstanceData(Vec::new()),
+        InstancedVoxels,
+        NoFrustumCulling,
+        CVoxelComponent { inner: cvoxel },
+    ));
+}
+
+pub struct VoxelInstancingPlugin;
+
+impl Plugin for VoxelInstancingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ExtractComponentPlugin::<VoxelInstanceData>::default())
+            .add_systems(Update, update_instances_from_cvoxel);
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app
+            .add_render_command::<Transparent3d, DrawVoxelInstanced>()
+            .init_resource::<SpecializedMeshPipelines<VoxelInstancedPipeline>>()
+            .add_systems(
+                Render,
+                (
+                    queue_voxel_instanced.in_set(RenderSet::QueueMeshes),
+                    prepare_instance_buffers.in_set(RenderSet::PrepareResources),
+                ),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app.init_resource::<VoxelInstancedPipeline>();
+    }
+}
+
+#[derive(Resource)]
+struct VoxelInstancedPipeline {
+    mesh_pipeline: MeshPipeline,
+    shader: Handle<Shader>,
+}
+
+impl FromWorld for VoxelInstancedPipeline {
+    fn from_world(world: &mut World) -> Self {
+        VoxelInstancedPipeline {
+            mesh_pipeline: world.resource::<MeshPipeline>().clone(),
+            shader: world.resource::<AssetServer>().load("shaders/voxel_instancing.wgsl"),
+        }
+    }
+}
+
+impl SpecializedMeshPipeline for VoxelInstancedPipeline {
+    type Key = MeshPipelineKey;
+
+    fn specialize(
+        &self,
+        key: Self::Key,
+        layout: &MeshVertexBufferLayoutRef,
+    ) -> Result<RenderPipelineDescriptor, SpecializedMeshPipelineError> {
+        let mut descriptor = self.mesh_pipeline.specialize(key, layout)?;
+        descriptor.vertex.shader = self.shader.clone();
+        descriptor.vertex.buffers.push(VertexBufferLayout {
+            array_stride: std::mem::size_of::<InstanceData>() as u64,
+            step_mode: VertexStepMode::Instance,
+            attributes: vec![
+                // position + scale, packed as a vec4.
+                VertexAttribute {
+                    format: VertexFormat::Float32x4,
+                    offset: 0,
+                    shader_location: 3,
+                },
+                // color.
+                VertexAttribute {
+                    format: VertexFormat::Float32x4,
+                    offset: VertexFormat::Float32x4.size(),
+                    shader_location: 4,
+                },
+            ],
+        });
+        if let Some(fragment) = &mut descriptor.fragment {
+            fragment.shader = self.shader.clone();
+        }
+        Ok(descriptor)
+    }
+}
+
+fn queue_voxel_instanced(
+    pipeline_cache: Res<PipelineCache>,
+    pipeline: Res<VoxelInstancedPipeline>,
+    mut pipelines: ResMut<SpecializedMeshPipelines<VoxelInstancedPipeline>>,
+    meshes: Res<RenderAssets<Mesh>>,
+    render_mesh_instances: Res<RenderMeshInstances>,
+    draw_functions: Res<DrawFunctions<Transparent3d>>,
+    mut phases: ResMut<ViewSortedRenderPhases<Transparent3d>>,
+    instanced: Query<Entity, With<InstancedVoxels>>,
+    views: Query<(Entity, &ExtractedView)>,
+) {
+    let draw_custom = draw_functions.read().id::<DrawVoxelInstanced>();
+
+    for (view_entity, view) in &views {
+        let Some(phase) = phases.get_mut(&view_entity) else {
+            continue;
+        };
+        let key = MeshPipelineKey::from_msaa_samples(1) | MeshPipelineKey::from_hdr(view.hdr);
+
+        for entity in &instanced {
+            let Some(mesh_instance) = render_mesh_instances.get(&entity) else {
+                continue;
+            };
+            let Some(mesh) = meshes.get(mesh_instance.mesh_asset_id) else {
+                continue;
+            };
+            let Ok(pipeline_id) = pipelines.specialize(&pipeline_cache, &pipeline, key, &mesh.layout) else {
+                continue;
+            };
+
+            phase.add(Transparent3d {
+                entity,
+                pipeline: pipeline_id,
+                draw_function: draw_custom,
+                distance: 0.0,
+                batch_range: 0..1,
+                dynamic_offset: None,
+            });
+        }
+    }
+}
+
+/// Render-world-only GPU buffer backing one entity's instance data, uploaded
+/// by [`prepare_instance_buffers`] whenever the main-world `VoxelInstanceData`
+/// changes.
+#[derive(Component)]
+struct InstanceBuffer {
+    buffer: Buffer,
+    length: usize,
+}
+
+fn prepare_instance_buffers(
+    mut commands: Commands,
+    query: Query<(Entity, &VoxelInstanceData), Changed<VoxelInstanceData>>,
+    render_device: Res<RenderDevice>,
+) {
+    for (entity, instances) in &query {
+        let buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("voxel instance buffer"),
+            contents: bytemuck::cast_slice(&instances.0),
+            usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
+        });
+        commands.entity(entity).insert(InstanceBuffer {
+            buffer,
+            length: instances.0.len(),
+        });
+    }
+}
+
+type DrawVoxelInstanced = (
+    SetItemPipeline,
+    SetMeshViewBindGroup<0>,
+    SetMeshBindGroup<1>,
+    DrawMeshInstanced,
+);
+
+struct DrawMeshInstanced;
+
+impl<P: PhaseItem> RenderCommand<P> for DrawMeshInstanced {
+    type Param = (SRes<RenderAssets<Mesh>>, SRes<RenderMeshInstances>);
+    type ViewQuery = ();
+    type ItemQuery = Read<InstanceBuffer>;
+
+    fn render<'w>(
+        item: &P,
+        _view: (),
+        instance_buffer: Option<&'w InstanceBuffer>,
+        (meshes, render_mesh_instances): SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let Some(instance_buffer) = instance_buffer else {
+            return RenderCommandResult::Failure;
+        };
+        let Some(mesh_instance) = render_mesh_instances.get(&item.entity()) else {
+            return RenderCommandResult::Failure;
+        };
+        let Some(gpu_mesh) = meshes.into_inner().get(mesh_instance.mesh_asset_id) else {
+            return RenderCommandResult::Failure;
+        };
+
+        pass.set_vertex_buffer(0, gpu_mesh.vertex_buffer.slice(..));
+        pass.set_vertex_buffer(1, instance_buffer.buffer.slice(..));
+
+        match &gpu_mesh.buffer_info {
+            GpuBufferInfo::Indexed { buffer, index_format, count } => {
+                pass.set_index_buffer(buffer.slice(..), 0, *index_format);
+                pass.draw_indexed(0..*count, 0, 0..instance_buffer.length as u32);
+            }
+            GpuBufferInfo::NonIndexed => {
+                pass.draw(0..gpu_mesh.vertex_count, 0..instance_buffer.length as u32);
+            }
+        }
+        RenderCommandResult::Success
+    }
+}
@@ -10,16 +10,21 @@ use bevy_panorbit_camera::{PanOrbitCamera, PanOrbitCameraPlugin};
 use cvoxel::CVoxels;
 use nalgebra::{Point3, UnitQuaternion, Vector3};
 
+mod instancing;
+use instancing::VoxelInstancingPlugin;
+
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
         .add_plugins(EguiPlugin)
         .add_plugins(PanOrbitCameraPlugin)
+        .add_plugins(VoxelInstancingPlugin)
         .add_systems(Startup, setup)
         .add_systems(Update, update_from_cvoxel_transform)
         .add_systems(Update, draw_voxel_aabb)
         .add_systems(Update, draw_intersection_aabb)
         .add_systems(Update, draw_intersecting_voxel)
+        .add_systems(Update, draw_picked_voxel)
         .add_systems(Update, ui)
         .run();
 }
@@ -30,8 +35,8 @@ struct AppSettings {
 }
 
 #[derive(Component)]
-struct CVoxelComponent {
-    inner: CVoxels,
+pub(crate) struct CVoxelComponent {
+    pub(crate) inner: CVoxels,
 }
 
 fn isometry_scale_to_transform(
@@ -88,7 +93,7 @@ fn draw_intersection_aabb(voxels: Query<&CVoxelComponent>, mut gizmos: Gizmos) {
     }
 }
 
-fn draw_single_voxel_in_object(index: usize, cvoxel: &CVoxels, gizmos: &mut Gizmos) {
+fn draw_single_voxel_in_object(index: usize, cvoxel: &CVoxels, color: Color, gizmos: &mut Gizmos) {
     let z = index / cvoxel.area;
     let left = index % cvoxel.area;
     let y = left / cvoxel.shape.x;
@@ -102,7 +107,7 @@ fn draw_single_voxel_in_object(index: usize, cvoxel: &CVoxels, gizmos: &mut Gizm
     isometry = cvoxel.transform * isometry;
     let transform = isometry_scale_to_transform(&isometry, &voxel_size);
 
-    gizmos.cuboid(transform, Color::linear_rgb(1.0, 0.0, 0.0));
+    gizmos.cuboid(transform, color);
 }
 
 fn draw_intersecting_voxel(voxels: Query<&CVoxelComponent>, mut gizmos: Gizmos) {
@@ -112,13 +117,56 @@ fn draw_intersecting_voxel(voxels: Query<&CVoxelComponent>, mut gizmos: Gizmos)
                 continue;
             }
             if let Some((i1, i2)) = ci.inner.intersected(&cj.inner) {
-                draw_single_voxel_in_object(i1, &ci.inner, &mut gizmos);
-                draw_single_voxel_in_object(i2, &cj.inner, &mut gizmos);
+                draw_single_voxel_in_object(i1, &ci.inner, Color::linear_rgb(1.0, 0.0, 0.0), &mut gizmos);
+                draw_single_voxel_in_object(i2, &cj.inner, Color::linear_rgb(1.0, 0.0, 0.0), &mut gizmos);
             }
         }
     }
 }
 
+/// Casts a ray from the camera through the cursor and highlights the first
+/// voxel it hits, giving a voxel-cursor / picking workflow over the demo's
+/// objects.
+fn draw_picked_voxel(
+    windows: Query<&Window>,
+    camera: Query<(&Camera, &GlobalTransform)>,
+    voxels: Query<&CVoxelComponent>,
+    mut gizmos: Gizmos,
+) {
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let Some(cursor) = window.cursor_position() else {
+        return;
+    };
+    let Ok((camera, camera_transform)) = camera.get_single() else {
+        return;
+    };
+    let Ok(ray) = camera.viewport_to_world(camera_transform, cursor) else {
+        return;
+    };
+
+    let origin = Point3::new(ray.origin.x, ray.origin.y, ray.origin.z);
+    let dir = Vector3::new(ray.direction.x, ray.direction.y, ray.direction.z);
+
+    let mut closest: Option<(f32, usize, &CVoxels)> = None;
+    for cvoxel in voxels.iter() {
+        if let Some(hit) = cvoxel.inner.raycast(origin, dir) {
+            let is_closer = match closest {
+                Some((t, ..)) => hit.t < t,
+                None => true,
+            };
+            if is_closer {
+                closest = Some((hit.t, hit.index, &cvoxel.inner));
+            }
+        }
+    }
+
+    if let Some((_, index, cvoxel)) = closest {
+        draw_single_voxel_in_object(index, cvoxel, Color::linear_rgb(0.0, 0.5, 1.0), &mut gizmos);
+    }
+}
+
 fn voxelize_mesh(mesh: &Mesh, dx: f32) -> Option<CVoxels> {
     let mesh_attr = mesh.attribute(Mesh::ATTRIBUTE_POSITION).unwrap();
     if let VertexAttributeValues::Float32x3(v) = mesh_attr {
@@ -135,8 +183,12 @@ fn voxelize_mesh(mesh: &Mesh, dx: f32) -> Option<CVoxels> {
     }
 }
 
-fn cvoxel_surface_mesh(voxels: &CVoxels) -> Mesh {
-    let surface_mesh = voxels.surface_mesh();
+fn cvoxel_surface_mesh(voxels: &CVoxels, smooth: bool) -> Mesh {
+    let surface_mesh = if smooth {
+        voxels.smooth_surface_mesh()
+    } else {
+        voxels.surface_mesh()
+    };
     Mesh::new(
         PrimitiveTopology::TriangleList,
         RenderAssetUsages::default(),
@@ -147,6 +199,8 @@ fn cvoxel_surface_mesh(voxels: &CVoxels) -> Mesh {
 }
 
 fn ui(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
     mut voxels: Query<(&mut CVoxelComponent, &mut Visibility)>,
     mut contexts: EguiContexts,
     mut panorbit: Query<&mut PanOrbitCamera>,
@@ -157,6 +211,13 @@ fn ui(
         // visualization
         ui.checkbox(&mut settings.show_bounding_box, "Show Bounding Box");
 
+        if ui.button("Spawn Instanced Sphere").clicked() {
+            let mesh = Sphere::new(0.3).mesh().build();
+            if let Some(cvoxel) = voxelize_mesh(&mesh, 0.05) {
+                instancing::spawn_instanced(&mut commands, &mut meshes, cvoxel);
+            }
+        }
+
         // controls
         for (i, (mut cvoxel, visibility)) in voxels.iter_mut().enumerate() {
             let transform = &mut cvoxel.inner.transform;
@@ -250,20 +311,21 @@ fn setup(
         ..Default::default()
     });
 
-    // meshes
+    // meshes. The capsule keeps its blocky surface; the sphere and torus use
+    // Marching Cubes so their curved surfaces don't look voxelated.
     let shapes = [
-        Capsule3d::new(0.3, 0.7).mesh().build(),
-        Sphere::new(0.3).mesh().build(),
-        Torus::new(0.2, 0.5).mesh().build(),
+        (Capsule3d::new(0.3, 0.7).mesh().build(), false),
+        (Sphere::new(0.3).mesh().build(), true),
+        (Torus::new(0.2, 0.5).mesh().build(), true),
     ];
 
     // voxel objects
     let dx = 0.05;
     for i in 0..shapes.len() {
-        let mesh = &shapes[i];
+        let (mesh, smooth) = &shapes[i];
         let mut cvoxel = voxelize_mesh(mesh, dx).unwrap();
         cvoxel.transform.translation.x = (i as f32 + 0.5 - shapes.len() as f32 * 0.5) * 0.9;
-        let surface_mesh = cvoxel_surface_mesh(&cvoxel);
+        let surface_mesh = cvoxel_surface_mesh(&cvoxel, *smooth);
         commands.spawn((
             PbrBundle {
                 mesh: meshes.add(surface_mesh),
@@ -0,0 +1,143 @@
+//! Building a [`CVoxels`] grid from a triangle mesh.
+
+use nalgebra::Vector3;
+
+use crate::{CVoxels, Shape, Voxel};
+
+impl CVoxels {
+    /// Voxelizes an indexed triangle mesh at resolution `dx`.
+    ///
+    /// Each voxel whose center lies within three quarters of a cell (`0.75 *
+    /// dx`) of any triangle is
+    /// marked solid, which is enough to give thin-walled meshes (like the
+    /// primitives spawned in the demo) a closed voxel shell. Returns `None`
+    /// for an empty mesh.
+    pub fn from_indexed_mesh<I>(vertices: &[[f32; 3]], indices: &[I], dx: f32) -> Option<Self>
+    where
+        I: Copy,
+        u32: From<I>,
+    {
+        let triangles: Vec<[Vector3<f32>; 3]> = indices
+            .chunks_exact(3)
+            .map(|tri| {
+                [
+                    Vector3::from(vertices[u32::from(tri[0]) as usize]),
+                    Vector3::from(vertices[u32::from(tri[1]) as usize]),
+                    Vector3::from(vertices[u32::from(tri[2]) as usize]),
+                ]
+            })
+            .collect();
+        Self::from_triangles(&triangles, dx)
+    }
+
+    /// Voxelizes a flat (non-indexed) triangle soup at resolution `dx`.
+    pub fn from_trimesh(vertices: &[[f32; 3]], dx: f32) -> Option<Self> {
+        let triangles: Vec<[Vector3<f32>; 3]> = vertices
+            .chunks_exact(3)
+            .map(|tri| [Vector3::from(tri[0]), Vector3::from(tri[1]), Vector3::from(tri[2])])
+            .collect();
+        Self::from_triangles(&triangles, dx)
+    }
+
+    fn from_triangles(triangles: &[[Vector3<f32>; 3]], dx: f32) -> Option<Self> {
+        if triangles.is_empty() {
+            return None;
+        }
+
+        let mut min = triangles[0][0];
+        let mut max = triangles[0][0];
+        for tri in triangles {
+            for v in tri {
+                min = min.zip_map(v, f32::min);
+                max = max.zip_map(v, f32::max);
+            }
+        }
+
+        let extent = max - min;
+        let shape = Shape {
+            x: ((extent.x / dx).ceil() as usize + 1).max(1),
+            y: ((extent.y / dx).ceil() as usize + 1).max(1),
+            z: ((extent.z / dx).ceil() as usize + 1).max(1),
+        };
+        let mut voxels = CVoxels::new(shape, dx);
+
+        for z in 0..shape.z {
+            for y in 0..shape.y {
+                for x in 0..shape.x {
+                    let world_center = min + Vector3::new(
+                        (x as f32 + 0.5) * dx,
+                        (y as f32 + 0.5) * dx,
+                        (z as f32 + 0.5) * dx,
+                    );
+                    let distance = triangles
+                        .iter()
+                        .map(|tri| point_triangle_distance(world_center, tri))
+                        .fold(f32::INFINITY, f32::min);
+                    if distance <= dx * 0.75 {
+                        let index = voxels.linear_index(x, y, z);
+                        voxels.voxels.set(
+                            index,
+                            Some(Voxel {
+                                color: [1.0, 1.0, 1.0, 1.0],
+                            }),
+                        );
+                    }
+                }
+            }
+        }
+
+        Some(voxels)
+    }
+}
+
+/// Distance from `p` to the closest point on triangle `tri`, clamped to the
+/// triangle (not just its plane).
+fn point_triangle_distance(p: Vector3<f32>, tri: &[Vector3<f32>; 3]) -> f32 {
+    let [a, b, c] = *tri;
+    let ab = b - a;
+    let ac = c - a;
+    let ap = p - a;
+
+    let d1 = ab.dot(&ap);
+    let d2 = ac.dot(&ap);
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return (p - a).norm();
+    }
+
+    let bp = p - b;
+    let d3 = ab.dot(&bp);
+    let d4 = ac.dot(&bp);
+    if d3 >= 0.0 && d4 <= d3 {
+        return (p - b).norm();
+    }
+
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        let v = d1 / (d1 - d3);
+        return (p - (a + ab * v)).norm();
+    }
+
+    let cp = p - c;
+    let d5 = ab.dot(&cp);
+    let d6 = ac.dot(&cp);
+    if d6 >= 0.0 && d5 <= d6 {
+        return (p - c).norm();
+    }
+
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        let w = d2 / (d2 - d6);
+        return (p - (a + ac * w)).norm();
+    }
+
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && (d4 - d3) >= 0.0 && (d5 - d6) >= 0.0 {
+        let w = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+        return (p - (b + (c - b) * w)).norm();
+    }
+
+    let denom = 1.0 / (va + vb + vc);
+    let v = vb * denom;
+    let w = vc * denom;
+    (p - (a + ab * v + ac * w)).norm()
+}
@@ -0,0 +1,61 @@
+//! Per-voxel instance data, as an alternative to baking a single surface
+//! [`SurfaceMesh`](crate::SurfaceMesh) per [`CVoxels`] object.
+
+use nalgebra::Vector3;
+
+use crate::CVoxels;
+
+impl CVoxels {
+    /// Local-space center and color of every solid voxel, one entry per
+    /// voxel. Intended for a renderer that draws each voxel as a unit cube
+    /// instance (transform + color) rather than a single surface mesh.
+    pub fn instances(&self) -> impl Iterator<Item = (Vector3<f32>, [f32; 4])> + '_ {
+        self.voxels.iter_solid().map(move |(index, voxel)| {
+            let (x, y, z) = self.cell_of(index);
+            (self.cell_center(x, y, z), voxel.color)
+        })
+    }
+
+    /// Like [`Self::instances`], but skips voxels with no exposed face (i.e.
+    /// fully interior voxels), which are never visible.
+    pub fn surface_instances(&self) -> impl Iterator<Item = (Vector3<f32>, [f32; 4])> + '_ {
+        self.voxels.iter_solid().filter_map(move |(index, voxel)| {
+            let (x, y, z) = self.cell_of(index);
+            self.is_surface_voxel(x, y, z)
+                .then(|| (self.cell_center(x, y, z), voxel.color))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CVoxels, Shape, Voxel};
+
+    #[test]
+    fn instances_yields_one_entry_per_solid_voxel() {
+        let shape = Shape { x: 3, y: 3, z: 3 };
+        let mut voxels = CVoxels::new(shape, 1.0);
+        for (x, y, z) in [(0, 0, 0), (1, 1, 1), (2, 2, 2)] {
+            let index = voxels.linear_index(x, y, z);
+            voxels.voxels.set(index, Some(Voxel { color: [1.0, 0.0, 0.0, 1.0] }));
+        }
+        assert_eq!(voxels.instances().count(), 3);
+    }
+
+    #[test]
+    fn surface_instances_skips_the_fully_interior_voxel() {
+        let shape = Shape { x: 3, y: 3, z: 3 };
+        let mut voxels = CVoxels::new(shape, 1.0);
+        for z in 0..shape.z {
+            for y in 0..shape.y {
+                for x in 0..shape.x {
+                    let index = voxels.linear_index(x, y, z);
+                    voxels.voxels.set(index, Some(Voxel { color: [1.0, 0.0, 0.0, 1.0] }));
+                }
+            }
+        }
+        // A fully-solid 3x3x3 block has exactly one interior voxel: (1,1,1).
+        assert_eq!(voxels.instances().count(), 27);
+        assert_eq!(voxels.surface_instances().count(), 26);
+    }
+}
@@ -0,0 +1,91 @@
+//! Broad/narrow-phase overlap queries between two [`CVoxels`] objects.
+
+use nalgebra::{Point3, Vector3};
+
+use crate::CVoxels;
+
+/// An axis-aligned box, used for the broad-phase overlap region between two
+/// voxel objects.
+#[derive(Debug, Clone, Copy)]
+pub struct Aabb {
+    pub min: Point3<f32>,
+    pub max: Point3<f32>,
+}
+
+impl Aabb {
+    pub fn middle(&self) -> Point3<f32> {
+        nalgebra::center(&self.min, &self.max)
+    }
+
+    pub fn size(&self) -> Vector3<f32> {
+        self.max - self.min
+    }
+}
+
+impl CVoxels {
+    /// World-space AABB of this grid (from `transform` and `half_size`: the
+    /// axis-aligned box enclosing the rotated grid, used for broad-phase
+    /// checks).
+    fn world_aabb(&self) -> Aabb {
+        let center = self.transform.translation.vector;
+        // Conservative bound: the half-size rotated into world space can only
+        // be as large as the sum of its axis components.
+        let extent = self.transform.rotation.to_rotation_matrix().matrix().abs() * self.half_size;
+        Aabb {
+            min: Point3::from(center - extent),
+            max: Point3::from(center + extent),
+        }
+    }
+
+    /// World-space AABB of the region where `self` and `other`'s bounding
+    /// boxes overlap, or `None` if they don't.
+    pub fn intersection_aabb(&self, other: &CVoxels) -> Option<Aabb> {
+        let a = self.world_aabb();
+        let b = other.world_aabb();
+        let min = a.min.coords.zip_map(&b.min.coords, f32::max);
+        let max = a.max.coords.zip_map(&b.max.coords, f32::min);
+        if min.x <= max.x && min.y <= max.y && min.z <= max.z {
+            Some(Aabb {
+                min: Point3::from(min),
+                max: Point3::from(max),
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Finds one pair of mutually-solid voxels, one from each object, whose
+    /// cells overlap in world space. Returns their linear indices into
+    /// `self` and `other` respectively.
+    pub fn intersected(&self, other: &CVoxels) -> Option<(usize, usize)> {
+        let aabb = self.intersection_aabb(other)?;
+        for (index, _voxel) in self.voxels.iter_solid() {
+            let (x, y, z) = self.cell_of(index);
+            let world_center = self.transform * nalgebra::Point3::from(self.cell_center(x, y, z));
+            if world_center.x < aabb.min.x
+                || world_center.x > aabb.max.x
+                || world_center.y < aabb.min.y
+                || world_center.y > aabb.max.y
+                || world_center.z < aabb.min.z
+                || world_center.z > aabb.max.z
+            {
+                continue;
+            }
+            let local = other.transform.inverse_transform_point(&world_center);
+            let coords = (local.coords + other.half_size) / other.dx;
+            let (ox, oy, oz) = (
+                coords.x.floor(),
+                coords.y.floor(),
+                coords.z.floor(),
+            );
+            if ox < 0.0 || oy < 0.0 || oz < 0.0 {
+                continue;
+            }
+            let (ox, oy, oz) = (ox as usize, oy as usize, oz as usize);
+            if other.occupied(ox, oy, oz) {
+                return Some((index, other.linear_index(ox, oy, oz)));
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,141 @@
+//! `cvoxel`: rigid voxel objects with meshing, intersection and collision
+//! (contact) queries, used by the `pvoxel` demos.
+//!
+//! A [`CVoxels`] is a dense grid of cubic cells of size `dx`, centered on its
+//! own [`nalgebra::Isometry3`] transform so it can be moved and rotated like
+//! any other rigid body while keeping its voxel data axis-aligned in local
+//! space.
+
+use nalgebra::{Isometry3, Vector3};
+
+mod construct;
+mod contact;
+mod csg;
+mod greedy_mesh;
+mod instances;
+mod intersect;
+mod marching_cubes;
+mod mc_tables;
+mod mesh;
+mod raycast;
+mod storage;
+
+pub use contact::*;
+pub use csg::*;
+pub use intersect::*;
+pub use mesh::*;
+pub use raycast::*;
+pub use storage::*;
+
+/// Number of voxels along each axis of a [`CVoxels`] grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Shape {
+    pub x: usize,
+    pub y: usize,
+    pub z: usize,
+}
+
+impl Shape {
+    pub fn len(&self) -> usize {
+        self.x * self.y * self.z
+    }
+}
+
+/// A single solid voxel cell, carrying the color it was set with.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Voxel {
+    pub color: [f32; 4],
+}
+
+/// A rigid grid of cubic voxels.
+///
+/// Cells are addressed densely, `x`-fastest, then `y`, then `z`, so the
+/// linear index of cell `(x, y, z)` is `x + y * shape.x + z * area` -
+/// regardless of whether `voxels` is backed by [`DenseStorage`] (one slot per
+/// cell) or [`SparseStorage`] (chunked, allocated on demand). `None` entries
+/// are empty space; `Some(voxel)` entries are solid.
+#[derive(Clone)]
+pub struct CVoxels {
+    pub shape: Shape,
+    /// `shape.x * shape.y`, cached because it is used on every index lookup.
+    pub area: usize,
+    /// Edge length of a single voxel cube, in local/world units.
+    pub dx: f32,
+    /// Half extent of the whole grid along each axis, `shape * dx / 2`.
+    pub half_size: Vector3<f32>,
+    /// World transform of the grid's local origin (its center).
+    pub transform: Isometry3<f32>,
+    pub voxels: Box<dyn VoxelStorage>,
+}
+
+impl std::fmt::Debug for CVoxels {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CVoxels")
+            .field("shape", &self.shape)
+            .field("dx", &self.dx)
+            .field("transform", &self.transform)
+            .finish_non_exhaustive()
+    }
+}
+
+impl CVoxels {
+    /// Creates an all-empty, densely-backed grid of the given shape and cell
+    /// size, centered at the identity transform.
+    pub fn new(shape: Shape, dx: f32) -> Self {
+        Self::with_storage(shape, dx, Box::new(DenseStorage::new(shape)))
+    }
+
+    /// Creates an all-empty grid backed by the sparse chunked storage, for
+    /// volumes expected to be mostly empty.
+    pub fn new_sparse(shape: Shape, dx: f32) -> Self {
+        Self::with_storage(shape, dx, Box::new(SparseStorage::new(shape)))
+    }
+
+    fn with_storage(shape: Shape, dx: f32, voxels: Box<dyn VoxelStorage>) -> Self {
+        let area = shape.x * shape.y;
+        let half_size = Vector3::new(shape.x as f32, shape.y as f32, shape.z as f32) * dx * 0.5;
+        CVoxels {
+            voxels,
+            shape,
+            area,
+            dx,
+            half_size,
+            transform: Isometry3::identity(),
+        }
+    }
+
+    /// Linear index of cell `(x, y, z)`. Does not bounds-check.
+    #[inline]
+    pub fn linear_index(&self, x: usize, y: usize, z: usize) -> usize {
+        x + y * self.shape.x + z * self.area
+    }
+
+    /// Inverse of [`Self::linear_index`].
+    #[inline]
+    pub fn cell_of(&self, index: usize) -> (usize, usize, usize) {
+        let z = index / self.area;
+        let left = index % self.area;
+        let y = left / self.shape.x;
+        let x = left % self.shape.x;
+        (x, y, z)
+    }
+
+    /// Returns the voxel at `(x, y, z)`, or `None` if it is empty or out of
+    /// bounds.
+    pub fn get(&self, x: usize, y: usize, z: usize) -> Option<Voxel> {
+        if x >= self.shape.x || y >= self.shape.y || z >= self.shape.z {
+            return None;
+        }
+        self.voxels.get(self.linear_index(x, y, z))
+    }
+
+    /// Whether cell `(x, y, z)` is solid. Out-of-bounds cells are empty.
+    pub fn occupied(&self, x: usize, y: usize, z: usize) -> bool {
+        self.get(x, y, z).is_some()
+    }
+
+    /// Local-space position of the center of cell `(x, y, z)`.
+    pub fn cell_center(&self, x: usize, y: usize, z: usize) -> Vector3<f32> {
+        Vector3::new(x as f32, y as f32, z as f32).add_scalar(0.5) * self.dx - self.half_size
+    }
+}
@@ -0,0 +1,166 @@
+//! Smooth surface extraction via Marching Cubes (Transvoxel-family), as an
+//! alternative to the blocky [`CVoxels::surface_mesh`].
+
+use nalgebra::Vector3;
+
+use crate::mc_tables::{EDGE_TABLE, TRI_TABLE};
+use crate::{CVoxels, SurfaceMesh};
+
+/// Local corner offsets in the standard Marching Cubes winding order.
+const CORNER_OFFSETS: [(isize, isize, isize); 8] = [
+    (0, 0, 0),
+    (1, 0, 0),
+    (1, 1, 0),
+    (0, 1, 0),
+    (0, 0, 1),
+    (1, 0, 1),
+    (1, 1, 1),
+    (0, 1, 1),
+];
+
+/// Which two corners each of the 12 cube edges connects.
+const EDGE_CORNERS: [(usize, usize); 12] = [
+    (0, 1),
+    (1, 2),
+    (2, 3),
+    (3, 0),
+    (4, 5),
+    (5, 6),
+    (6, 7),
+    (7, 4),
+    (0, 4),
+    (1, 5),
+    (2, 6),
+    (3, 7),
+];
+
+impl CVoxels {
+    /// Smooth alternative to [`Self::surface_mesh`], produced by running
+    /// Marching Cubes over a signed scalar field derived from occupancy.
+    ///
+    /// Each cell corner samples `+1` for empty (and for any corner outside
+    /// the grid, so the volume boundary closes cleanly instead of cracking
+    /// open), `-1` for solid. The 8 corner signs of a cell select a case
+    /// from the standard 256-entry edge/triangle tables; crossed edges are
+    /// linearly interpolated by the ratio of their endpoint scalars, and
+    /// each edge vertex takes the color of the edge's solid endpoint.
+    pub fn smooth_surface_mesh(&self) -> SurfaceMesh {
+        let mut mesh = SurfaceMesh::default();
+
+        // Marching Cubes walks *between* voxel centers, so there is one cell
+        // per (shape + 1) lattice of corners; a cell is considered only if at
+        // least one of its 8 corners lies inside the grid.
+        for z in -1..self.shape.z as isize {
+            for y in -1..self.shape.y as isize {
+                for x in -1..self.shape.x as isize {
+                    self.march_cell(x, y, z, &mut mesh);
+                }
+            }
+        }
+
+        mesh
+    }
+
+    fn march_cell(&self, x: isize, y: isize, z: isize, mesh: &mut SurfaceMesh) {
+        let mut scalars = [0.0f32; 8];
+        let mut colors = [[1.0f32; 4]; 8];
+        for (i, &(ox, oy, oz)) in CORNER_OFFSETS.iter().enumerate() {
+            match self.corner_voxel(x + ox, y + oy, z + oz) {
+                Some(color) => {
+                    scalars[i] = -1.0;
+                    colors[i] = color;
+                }
+                None => scalars[i] = 1.0,
+            }
+        }
+
+        let mut case_index = 0usize;
+        for (i, &s) in scalars.iter().enumerate() {
+            if s < 0.0 {
+                case_index |= 1 << i;
+            }
+        }
+
+        let edge_mask = EDGE_TABLE[case_index];
+        if edge_mask == 0 {
+            return;
+        }
+
+        let mut edge_vertex = [[0.0f32; 3]; 12];
+        let mut edge_color = [[0.0f32; 4]; 12];
+        for edge in 0..12 {
+            if edge_mask & (1 << edge) == 0 {
+                continue;
+            }
+            let (a, b) = EDGE_CORNERS[edge];
+            let pa = self.corner_position(x, y, z, CORNER_OFFSETS[a]);
+            let pb = self.corner_position(x, y, z, CORNER_OFFSETS[b]);
+            let t = scalars[a] / (scalars[a] - scalars[b]);
+            let p = pa + (pb - pa) * t;
+            edge_vertex[edge] = [p.x, p.y, p.z];
+            edge_color[edge] = if scalars[a] < 0.0 { colors[a] } else { colors[b] };
+        }
+
+        let case_triangles = &TRI_TABLE[case_index];
+        for tri in case_triangles.chunks_exact(3) {
+            if tri[0] < 0 {
+                break;
+            }
+            for &edge in tri {
+                let edge = edge as usize;
+                mesh.position.push(edge_vertex[edge]);
+                mesh.color.push(edge_color[edge]);
+            }
+        }
+    }
+
+    /// Local-space position of cell `(x, y, z)`'s corner `offset`, i.e. the
+    /// center of the voxel that corner samples, which may be negative or
+    /// beyond `shape` at the volume boundary.
+    fn corner_position(&self, x: isize, y: isize, z: isize, offset: (isize, isize, isize)) -> Vector3<f32> {
+        Vector3::new(
+            (x + offset.0) as f32,
+            (y + offset.1) as f32,
+            (z + offset.2) as f32,
+        )
+        .add_scalar(0.5)
+            * self.dx
+            - self.half_size
+    }
+
+    /// Color of the voxel covering a Marching Cubes corner, or `None` if
+    /// that corner is empty or outside the grid.
+    fn corner_voxel(&self, x: isize, y: isize, z: isize) -> Option<[f32; 4]> {
+        if x < 0 || y < 0 || z < 0 {
+            return None;
+        }
+        self.get(x as usize, y as usize, z as usize).map(|v| v.color)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CVoxels, Shape, Voxel};
+
+    #[test]
+    fn single_solid_voxel_produces_a_closed_surface() {
+        let shape = Shape { x: 3, y: 3, z: 3 };
+        let mut voxels = CVoxels::new(shape, 1.0);
+        let index = voxels.linear_index(1, 1, 1);
+        voxels.voxels.set(index, Some(Voxel { color: [1.0, 1.0, 1.0, 1.0] }));
+
+        let mesh = voxels.smooth_surface_mesh();
+
+        assert!(!mesh.position.is_empty());
+        assert_eq!(mesh.position.len(), mesh.color.len());
+        assert_eq!(mesh.position.len() % 3, 0);
+    }
+
+    #[test]
+    fn empty_grid_produces_no_surface() {
+        let shape = Shape { x: 2, y: 2, z: 2 };
+        let voxels = CVoxels::new(shape, 1.0);
+        let mesh = voxels.smooth_surface_mesh();
+        assert!(mesh.position.is_empty());
+    }
+}
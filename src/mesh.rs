@@ -0,0 +1,125 @@
+//! Turning a [`CVoxels`] grid into renderable triangle data.
+
+use crate::CVoxels;
+
+/// Flat vertex buffers ready to hand to a renderer (e.g. a Bevy `Mesh` via
+/// `ATTRIBUTE_POSITION` / `ATTRIBUTE_COLOR`). `position` and `color` are
+/// parallel arrays, one entry per vertex.
+#[derive(Debug, Clone, Default)]
+pub struct SurfaceMesh {
+    pub position: Vec<[f32; 3]>,
+    pub color: Vec<[f32; 4]>,
+}
+
+const FACE_DIRS: [(isize, isize, isize); 6] = [
+    (1, 0, 0),
+    (-1, 0, 0),
+    (0, 1, 0),
+    (0, -1, 0),
+    (0, 0, 1),
+    (0, 0, -1),
+];
+
+impl CVoxels {
+    /// Whether cell `(x, y, z)` is solid and has at least one exposed face,
+    /// i.e. it would contribute geometry to [`Self::surface_mesh`].
+    pub fn is_surface_voxel(&self, x: usize, y: usize, z: usize) -> bool {
+        if !self.occupied(x, y, z) {
+            return false;
+        }
+        FACE_DIRS.iter().any(|&(dx, dy, dz)| {
+            let (nx, ny, nz) = (x as isize + dx, y as isize + dy, z as isize + dz);
+            nx < 0 || ny < 0 || nz < 0 || !self.occupied(nx as usize, ny as usize, nz as usize)
+        })
+    }
+
+    /// Blocky per-voxel surface mesh: one quad per exposed cuboid face.
+    ///
+    /// A face is emitted whenever a solid voxel's neighbor in that direction
+    /// is empty or out of bounds, so the result is the closed outer skin of
+    /// the volume.
+    pub fn surface_mesh(&self) -> SurfaceMesh {
+        let mut mesh = SurfaceMesh::default();
+        let half = self.dx * 0.5;
+        for z in 0..self.shape.z {
+            for y in 0..self.shape.y {
+                for x in 0..self.shape.x {
+                    let Some(voxel) = self.get(x, y, z) else {
+                        continue;
+                    };
+                    let center = self.cell_center(x, y, z);
+                    for &(dx, dy, dz) in &FACE_DIRS {
+                        let (nx, ny, nz) = (
+                            x as isize + dx,
+                            y as isize + dy,
+                            z as isize + dz,
+                        );
+                        let neighbor_solid = nx >= 0
+                            && ny >= 0
+                            && nz >= 0
+                            && self.occupied(nx as usize, ny as usize, nz as usize);
+                        if neighbor_solid {
+                            continue;
+                        }
+                        let quad = face_quad(center, half, (dx, dy, dz));
+                        emit_face_triangles(&mut mesh, quad, voxel.color);
+                    }
+                }
+            }
+        }
+        mesh
+    }
+}
+
+/// The four corners of the cube face of the given half-extent `half` facing
+/// `dir` (one of the six axis directions), centered at `center`.
+fn face_quad(center: nalgebra::Vector3<f32>, half: f32, dir: (isize, isize, isize)) -> [[f32; 3]; 4] {
+    let c = center;
+    let corners: [[f32; 3]; 4] = match dir {
+        (1, 0, 0) => [
+            [c.x + half, c.y - half, c.z - half],
+            [c.x + half, c.y + half, c.z - half],
+            [c.x + half, c.y + half, c.z + half],
+            [c.x + half, c.y - half, c.z + half],
+        ],
+        (-1, 0, 0) => [
+            [c.x - half, c.y - half, c.z + half],
+            [c.x - half, c.y + half, c.z + half],
+            [c.x - half, c.y + half, c.z - half],
+            [c.x - half, c.y - half, c.z - half],
+        ],
+        (0, 1, 0) => [
+            [c.x - half, c.y + half, c.z - half],
+            [c.x - half, c.y + half, c.z + half],
+            [c.x + half, c.y + half, c.z + half],
+            [c.x + half, c.y + half, c.z - half],
+        ],
+        (0, -1, 0) => [
+            [c.x - half, c.y - half, c.z + half],
+            [c.x - half, c.y - half, c.z - half],
+            [c.x + half, c.y - half, c.z - half],
+            [c.x + half, c.y - half, c.z + half],
+        ],
+        (0, 0, 1) => [
+            [c.x + half, c.y - half, c.z + half],
+            [c.x + half, c.y + half, c.z + half],
+            [c.x - half, c.y + half, c.z + half],
+            [c.x - half, c.y - half, c.z + half],
+        ],
+        (0, 0, -1) => [
+            [c.x - half, c.y - half, c.z - half],
+            [c.x - half, c.y + half, c.z - half],
+            [c.x + half, c.y + half, c.z - half],
+            [c.x + half, c.y - half, c.z - half],
+        ],
+        _ => unreachable!("face direction must be a unit axis vector"),
+    };
+    corners
+}
+
+fn emit_face_triangles(mesh: &mut SurfaceMesh, quad: [[f32; 3]; 4], color: [f32; 4]) {
+    for &i in &[0usize, 1, 2, 0, 2, 3] {
+        mesh.position.push(quad[i]);
+        mesh.color.push(color);
+    }
+}
@@ -0,0 +1,193 @@
+//! Backing storage for a [`CVoxels`] grid's cell data, abstracted behind
+//! [`VoxelStorage`] so meshing and intersection code works the same whether
+//! the grid is densely allocated or sparsely chunked.
+
+use std::collections::HashMap;
+
+use crate::{Shape, Voxel};
+
+/// Side length, in cells, of one [`SparseStorage`] chunk along each axis.
+const CHUNK_SIZE: usize = 16;
+
+/// A storage backend for a fixed-[`Shape`] voxel grid, indexed by the same
+/// linear index as [`CVoxels::linear_index`].
+///
+/// `Send + Sync` is required so `Box<dyn VoxelStorage>` (and therefore
+/// `CVoxels`) can be stored in a Bevy `Component`, which requires
+/// `Send + Sync + 'static`.
+pub trait VoxelStorage: Send + Sync {
+    fn get(&self, index: usize) -> Option<Voxel>;
+    fn set(&mut self, index: usize, voxel: Option<Voxel>);
+    /// Every solid voxel as `(linear index, voxel)`, in no particular order.
+    /// Implementations should skip empty space rather than visiting every
+    /// cell, so this stays cheap on mostly-empty volumes.
+    fn iter_solid<'a>(&'a self) -> Box<dyn Iterator<Item = (usize, Voxel)> + 'a>;
+    fn clone_box(&self) -> Box<dyn VoxelStorage>;
+}
+
+impl Clone for Box<dyn VoxelStorage> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+/// The original flat layout: one `Vec` slot per cell, allocated up front.
+#[derive(Debug, Clone)]
+pub struct DenseStorage {
+    cells: Vec<Option<Voxel>>,
+}
+
+impl DenseStorage {
+    pub fn new(shape: Shape) -> Self {
+        DenseStorage {
+            cells: vec![None; shape.len()],
+        }
+    }
+}
+
+impl VoxelStorage for DenseStorage {
+    fn get(&self, index: usize) -> Option<Voxel> {
+        self.cells[index]
+    }
+
+    fn set(&mut self, index: usize, voxel: Option<Voxel>) {
+        self.cells[index] = voxel;
+    }
+
+    fn iter_solid<'a>(&'a self) -> Box<dyn Iterator<Item = (usize, Voxel)> + 'a> {
+        Box::new(
+            self.cells
+                .iter()
+                .enumerate()
+                .filter_map(|(index, voxel)| voxel.map(|v| (index, v))),
+        )
+    }
+
+    fn clone_box(&self) -> Box<dyn VoxelStorage> {
+        Box::new(self.clone())
+    }
+}
+
+/// Sparse chunked layout: a `HashMap` of fixed-size dense chunks keyed by
+/// chunk coordinate. A volume that is mostly air only allocates the chunks
+/// that actually contain a solid voxel, and a chunk that becomes fully empty
+/// after an edit is immediately dropped.
+#[derive(Debug, Clone)]
+pub struct SparseStorage {
+    shape: Shape,
+    chunks: HashMap<(usize, usize, usize), Vec<Option<Voxel>>>,
+}
+
+impl SparseStorage {
+    pub fn new(shape: Shape) -> Self {
+        SparseStorage {
+            shape,
+            chunks: HashMap::new(),
+        }
+    }
+
+    fn cell_of(&self, index: usize) -> (usize, usize, usize) {
+        let area = self.shape.x * self.shape.y;
+        let z = index / area;
+        let left = index % area;
+        let y = left / self.shape.x;
+        let x = left % self.shape.x;
+        (x, y, z)
+    }
+
+    /// Splits a cell coordinate into its chunk key and linear offset within
+    /// that chunk.
+    fn split(x: usize, y: usize, z: usize) -> ((usize, usize, usize), usize) {
+        let chunk = (x / CHUNK_SIZE, y / CHUNK_SIZE, z / CHUNK_SIZE);
+        let (lx, ly, lz) = (x % CHUNK_SIZE, y % CHUNK_SIZE, z % CHUNK_SIZE);
+        (chunk, lz * CHUNK_SIZE * CHUNK_SIZE + ly * CHUNK_SIZE + lx)
+    }
+}
+
+impl VoxelStorage for SparseStorage {
+    fn get(&self, index: usize) -> Option<Voxel> {
+        let (x, y, z) = self.cell_of(index);
+        let (chunk, local) = Self::split(x, y, z);
+        self.chunks.get(&chunk).and_then(|cells| cells[local])
+    }
+
+    fn set(&mut self, index: usize, voxel: Option<Voxel>) {
+        let (x, y, z) = self.cell_of(index);
+        let (chunk, local) = Self::split(x, y, z);
+        match voxel {
+            Some(v) => {
+                let cells = self
+                    .chunks
+                    .entry(chunk)
+                    .or_insert_with(|| vec![None; CHUNK_SIZE * CHUNK_SIZE * CHUNK_SIZE]);
+                cells[local] = Some(v);
+            }
+            None => {
+                let Some(cells) = self.chunks.get_mut(&chunk) else {
+                    return;
+                };
+                cells[local] = None;
+                if cells.iter().all(Option::is_none) {
+                    self.chunks.remove(&chunk);
+                }
+            }
+        }
+    }
+
+    fn iter_solid<'a>(&'a self) -> Box<dyn Iterator<Item = (usize, Voxel)> + 'a> {
+        let area = self.shape.x * self.shape.y;
+        let shape_x = self.shape.x;
+        Box::new(self.chunks.iter().flat_map(move |(&(cx, cy, cz), cells)| {
+            cells.iter().enumerate().filter_map(move |(local, voxel)| {
+                voxel.map(|v| {
+                    let lx = local % CHUNK_SIZE;
+                    let ly = (local / CHUNK_SIZE) % CHUNK_SIZE;
+                    let lz = local / (CHUNK_SIZE * CHUNK_SIZE);
+                    let (x, y, z) = (cx * CHUNK_SIZE + lx, cy * CHUNK_SIZE + ly, cz * CHUNK_SIZE + lz);
+                    (x + y * shape_x + z * area, v)
+                })
+            })
+        }))
+    }
+
+    fn clone_box(&self) -> Box<dyn VoxelStorage> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn setting_and_unsetting_a_voxel_drops_its_chunk() {
+        let shape = Shape { x: 32, y: 32, z: 32 };
+        let mut storage = SparseStorage::new(shape);
+        let voxel = Voxel { color: [1.0, 0.0, 0.0, 1.0] };
+
+        let index = 5 + 5 * shape.x + 5 * shape.x * shape.y;
+        storage.set(index, Some(voxel));
+        assert_eq!(storage.chunks.len(), 1);
+        assert_eq!(storage.get(index), Some(voxel));
+
+        storage.set(index, None);
+        assert!(storage.chunks.is_empty(), "chunk should be dropped once empty");
+        assert_eq!(storage.get(index), None);
+    }
+
+    #[test]
+    fn iter_solid_only_visits_set_voxels() {
+        let shape = Shape { x: 32, y: 32, z: 32 };
+        let mut storage = SparseStorage::new(shape);
+        let voxel = Voxel { color: [0.0, 1.0, 0.0, 1.0] };
+
+        let a = 1 + 1 * shape.x;
+        let b = 20 + 20 * shape.x + 20 * shape.x * shape.y;
+        storage.set(a, Some(voxel));
+        storage.set(b, Some(voxel));
+
+        let mut found: Vec<usize> = storage.iter_solid().map(|(index, _)| index).collect();
+        found.sort_unstable();
+        assert_eq!(found, vec![a, b]);
+    }
+}